@@ -0,0 +1,36 @@
+// Bitcoin secp256k1 bindings
+// Written in 2014 by
+//   Dawid Ciężarkiewicz
+//   Andrew Poelstra
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Constants
+//! Constants relating to the secp256k1 curve and the sizes of the
+//! objects that the library reads and writes.
+
+/// The size (in bytes) of a message
+pub const MESSAGE_SIZE: usize = 32;
+
+/// The size (in bytes) of a secret key
+pub const SECRET_KEY_SIZE: usize = 32;
+
+/// The size (in bytes) of an uncompressed public key
+pub const UNCOMPRESSED_PUBLIC_KEY_SIZE: usize = 65;
+
+/// The size (in bytes) of a compressed public key
+pub const COMPRESSED_PUBLIC_KEY_SIZE: usize = 33;
+
+/// The maximum size of a DER-encoded (non-compact) signature
+pub const MAX_SIGNATURE_SIZE: usize = 72;
+
+/// The size (in bytes) of a compact signature
+pub const MAX_COMPACT_SIGNATURE_SIZE: usize = 65;
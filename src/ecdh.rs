@@ -0,0 +1,63 @@
+// Bitcoin secp256k1 bindings
+// Written in 2014 by
+//   Dawid Ciężarkiewicz
+//   Andrew Poelstra
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Elliptic Curve Diffie-Hellman
+//! Shared-secret agreement between a public key and a secret key, the
+//! standard building block for encrypted messaging and stealth addresses
+//! over secp256k1.
+
+use libc::c_int;
+
+use super::{Error, Secp256k1, Verification};
+use key::{PublicKey, SecretKey};
+use ffi;
+
+/// A 32-byte shared secret, computed by hashing the x-coordinate of
+/// `scalar * point`
+pub struct SharedSecret([u8; 32]);
+impl_array_newtype!(SharedSecret, u8, 32);
+
+impl SharedSecret {
+    /// Computes an ECDH shared secret from a public key and a secret key.
+    /// Fails with `InvalidPublicKey` if `point` -- which `PublicKey::from_slice`
+    /// does not check lies on the curve -- turns out not to be a valid point.
+    /// Requires a verification-capable context: both this and `new_raw`
+    /// (via `PublicKey::mul_assign`) need the ecmult tables a `SignOnly`
+    /// context never allocates.
+    #[inline]
+    pub fn new<C: Verification>(secp: &Secp256k1<C>, point: &PublicKey, scalar: &SecretKey) -> Result<SharedSecret, Error> {
+        let mut ret = [0; 32];
+        unsafe {
+            let res = ffi::secp256k1_ecdh(secp.ctx, ret.as_mut_ptr(),
+                                          point.as_ptr(), point.len() as c_int,
+                                          scalar.as_ptr());
+            if res != 1 {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        Ok(SharedSecret(ret))
+    }
+
+    /// Computes the raw ECDH point `scalar * point`, in the same
+    /// compression as `point`, without hashing it -- for callers who
+    /// want to supply their own KDF.
+    #[inline]
+    pub fn new_raw<C: Verification>(secp: &Secp256k1<C>, point: &PublicKey, scalar: &SecretKey)
+                      -> Result<PublicKey, Error> {
+        let mut ret = point.clone();
+        try!(ret.mul_assign(secp, &scalar[..]));
+        Ok(ret)
+    }
+}
@@ -0,0 +1,189 @@
+// Bitcoin secp256k1 bindings
+// Written in 2014 by
+//   Dawid Ciężarkiewicz
+//   Andrew Poelstra
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # FFI bindings
+//! Direct bindings to the underlying C library functions. These should
+//! not be needed on average, the `Secp256k1` object should be used instead.
+#![allow(dead_code)]
+
+use libc::{c_int, c_uchar, c_uint, c_void};
+
+/// Flag for context to enable verification precomputation
+pub const SECP256K1_START_VERIFY: c_int = 1 << 0;
+/// Flag for context to enable signing precomputation
+pub const SECP256K1_START_SIGN: c_int = 1 << 1;
+
+/// Opaque data structure that holds a parameterized `secp256k1` context,
+/// i.e. the precomputed tables needed to sign and/or verify. Takes the
+/// place of the old process-global `Once`-guarded initialization; one of
+/// these must be created (and eventually destroyed) through
+/// `secp256k1_context_create`/`secp256k1_context_destroy` rather than
+/// assumed to exist globally. Never accessed directly, only through a
+/// pointer handed back and forth across the FFI boundary.
+pub enum Context {}
+
+/// A nonce generation function. Ordinary users of the library
+/// never need to call this themselves; it is used internally
+/// by `secp256k1_ecdsa_sign` et al.
+pub type NonceFn = extern "C" fn(nonce32: *mut c_uchar,
+                                  msg32: *const c_uchar,
+                                  key32: *const c_uchar,
+                                  attempt: c_uint,
+                                  data: *const c_void) -> c_int;
+
+#[link(name = "secp256k1")]
+extern "C" {
+    /// The default nonce-generation function, which uses a random
+    /// extra component in addition to the message and private key.
+    pub static secp256k1_nonce_function_default: NonceFn;
+    /// An RFC6979-compliant nonce-generation function that is
+    /// deterministic in the message and private key alone.
+    pub static secp256k1_nonce_function_rfc6979: NonceFn;
+
+    /// Creates a new context object, allocating only the precomputed
+    /// tables requested by `flags` (`SECP256K1_START_VERIFY`,
+    /// `SECP256K1_START_SIGN`, or both).
+    pub fn secp256k1_context_create(flags: c_int) -> *mut Context;
+
+    /// Performs a deep copy of a context object.
+    pub fn secp256k1_context_clone(ctx: *mut Context) -> *mut Context;
+
+    /// Destroys a context object, freeing its precomputed tables.
+    pub fn secp256k1_context_destroy(ctx: *mut Context);
+
+    /// Updates the context's internal blinding/randomization state from
+    /// `seed32`, hardening it against side-channel attacks that exploit
+    /// the precomputed tables. Safe to call repeatedly.
+    pub fn secp256k1_context_randomize(ctx: *mut Context,
+                                        seed32: *const c_uchar) -> c_int;
+
+    pub fn secp256k1_ecdsa_sign(ctx: *const Context,
+                                 msg32: *const c_uchar,
+                                 sig: *mut c_uchar,
+                                 siglen: *mut c_int,
+                                 seckey: *const c_uchar,
+                                 noncefn: NonceFn,
+                                 noncedata: *const c_void) -> c_int;
+
+    pub fn secp256k1_ecdsa_sign_compact(ctx: *const Context,
+                                         msg32: *const c_uchar,
+                                         sig64: *mut c_uchar,
+                                         seckey: *const c_uchar,
+                                         noncefn: NonceFn,
+                                         noncedata: *const c_void,
+                                         recid: *mut c_int) -> c_int;
+
+    pub fn secp256k1_ecdsa_verify(ctx: *const Context,
+                                   msg32: *const c_uchar,
+                                   sig: *const c_uchar,
+                                   siglen: c_int,
+                                   pubkey: *const c_uchar,
+                                   pubkeylen: c_int) -> c_int;
+
+    pub fn secp256k1_ecdsa_recover_compact(ctx: *const Context,
+                                            msg32: *const c_uchar,
+                                            sig64: *const c_uchar,
+                                            pubkey: *mut c_uchar,
+                                            pubkeylen: *mut c_int,
+                                            compressed: c_int,
+                                            recid: c_int) -> c_int;
+
+    pub fn secp256k1_ec_seckey_verify(ctx: *const Context,
+                                       seckey: *const c_uchar) -> c_int;
+
+    pub fn secp256k1_ec_pubkey_create(ctx: *const Context,
+                                       pubkey: *mut c_uchar,
+                                       pubkeylen: *mut c_int,
+                                       seckey: *const c_uchar,
+                                       compressed: c_int) -> c_int;
+
+    pub fn secp256k1_ec_pubkey_decompress(ctx: *const Context,
+                                           pubkey: *mut c_uchar,
+                                           pubkeylen: *mut c_int) -> c_int;
+
+    pub fn secp256k1_ec_pubkey_verify(ctx: *const Context,
+                                       pubkey: *const c_uchar,
+                                       pubkeylen: c_int) -> c_int;
+
+    /// Adds a tweak, modulo the curve order, to a secret key in place.
+    pub fn secp256k1_ec_privkey_tweak_add(ctx: *const Context,
+                                           seckey: *mut c_uchar,
+                                           tweak: *const c_uchar) -> c_int;
+
+    /// Multiplies a secret key in place by a tweak, modulo the curve order.
+    pub fn secp256k1_ec_privkey_tweak_mul(ctx: *const Context,
+                                           seckey: *mut c_uchar,
+                                           tweak: *const c_uchar) -> c_int;
+
+    /// Adds `tweak * G` to a public key in place.
+    pub fn secp256k1_ec_pubkey_tweak_add(ctx: *const Context,
+                                          pubkey: *mut c_uchar,
+                                          pubkeylen: c_int,
+                                          tweak: *const c_uchar) -> c_int;
+
+    /// Multiplies a public key in place by a tweak.
+    pub fn secp256k1_ec_pubkey_tweak_mul(ctx: *const Context,
+                                          pubkey: *mut c_uchar,
+                                          pubkeylen: c_int,
+                                          tweak: *const c_uchar) -> c_int;
+
+    /// Adds together `n` public keys (each possibly compressed or
+    /// uncompressed, per its own `pubkeylens` entry), writing the
+    /// resulting point into `pubkey` in the encoding selected by
+    /// `compressed`. Fails if the sum is the point at infinity.
+    pub fn secp256k1_ec_pubkey_combine(ctx: *const Context,
+                                        pubkey: *mut c_uchar,
+                                        pubkeylen: *mut c_int,
+                                        pubkeys: *const *const c_uchar,
+                                        pubkeylens: *const c_int,
+                                        n: c_int,
+                                        compressed: c_int) -> c_int;
+
+    /// Parses a strict DER-encoded signature into the library's internal
+    /// 64-byte compact (r, s) representation.
+    pub fn secp256k1_ecdsa_sig_parse(ctx: *const Context,
+                                      compact_sig: *mut c_uchar,
+                                      der: *const c_uchar,
+                                      derlen: c_int) -> c_int;
+
+    /// Parses a DER-encoded signature the same as `secp256k1_ecdsa_sig_parse`,
+    /// but tolerates the zero-padded and overlong-length encodings that show
+    /// up in historical, pre-BIP66 transactions.
+    pub fn secp256k1_ecdsa_sig_parse_lax(ctx: *const Context,
+                                          compact_sig: *mut c_uchar,
+                                          der: *const c_uchar,
+                                          derlen: c_int) -> c_int;
+
+    /// Serializes the library's internal 64-byte compact (r, s) representation
+    /// of a signature into strict DER.
+    pub fn secp256k1_ecdsa_sig_serialize(ctx: *const Context,
+                                          der: *mut c_uchar,
+                                          derlen: *mut c_int,
+                                          compact_sig: *const c_uchar) -> c_int;
+
+    /// Maps the S component of a signature, in the library's internal 64-byte
+    /// compact representation, into the lower half of the curve order in place.
+    /// Returns 1 if the signature was not already normalized, 0 otherwise.
+    pub fn secp256k1_ecdsa_sig_normalize(ctx: *const Context,
+                                          compact_sig: *mut c_uchar) -> c_int;
+
+    /// Computes an ECDH shared secret: hashes the x-coordinate of
+    /// `seckey * pubkey` into `output32`.
+    pub fn secp256k1_ecdh(ctx: *const Context,
+                           output32: *mut c_uchar,
+                           pubkey: *const c_uchar,
+                           pubkeylen: c_int,
+                           seckey: *const c_uchar) -> c_int;
+}
@@ -0,0 +1,441 @@
+// Bitcoin secp256k1 bindings
+// Written in 2014 by
+//   Dawid Ciężarkiewicz
+//   Andrew Poelstra
+//
+// To the extent possible under law, the author(s) have dedicated all
+// copyright and related and neighboring rights to this software to
+// the public domain worldwide. This software is distributed without
+// any warranty.
+//
+// You should have received a copy of the CC0 Public Domain Dedication
+// along with this software.
+// If not, see <http://creativecommons.org/publicdomain/zero/1.0/>.
+//
+
+//! # Secret and Public Keys
+//! The actual values that parameterize elliptic curve cryptography,
+//! `SecretKey` and `PublicKey`.
+
+use std::intrinsics::copy_nonoverlapping;
+use std::str::FromStr;
+use std::{fmt, ops};
+use libc::c_int;
+use rand::Rng;
+use serialize::hex::{FromHex, ToHex};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de;
+
+use super::{Error, Secp256k1};
+use constants;
+use ffi;
+
+/// A secret key, used as `x` in an ECDSA signature
+pub struct SecretKey([u8; constants::SECRET_KEY_SIZE]);
+impl_array_newtype!(SecretKey, u8, constants::SECRET_KEY_SIZE);
+
+impl SecretKey {
+    /// Creates a new random secret key
+    #[inline]
+    pub fn new<C, R: Rng>(secp: &Secp256k1<C>, rng: &mut R) -> SecretKey {
+        let mut ret = [0; constants::SECRET_KEY_SIZE];
+        loop {
+            rng.fill_bytes(&mut ret);
+            if unsafe { ffi::secp256k1_ec_seckey_verify(secp.ctx, ret.as_ptr()) } == 1 {
+                break;
+            }
+        }
+        SecretKey(ret)
+    }
+
+    /// Converts a `SECRET_KEY_SIZE`-byte slice to a secret key
+    #[inline]
+    pub fn from_slice<C>(secp: &Secp256k1<C>, data: &[u8]) -> Result<SecretKey, Error> {
+        match data.len() {
+            constants::SECRET_KEY_SIZE => {
+                let mut ret = [0; constants::SECRET_KEY_SIZE];
+                unsafe {
+                    if ffi::secp256k1_ec_seckey_verify(secp.ctx, data.as_ptr()) == 0 {
+                        return Err(Error::InvalidSecretKey);
+                    }
+                    copy_nonoverlapping(data.as_ptr(),
+                                        ret.as_mut_ptr(),
+                                        data.len());
+                }
+                Ok(SecretKey(ret))
+            }
+            _ => Err(Error::InvalidSecretKey)
+        }
+    }
+
+    /// Adds `other` to this secret key, modulo the curve order. This is
+    /// the scalar addition step of BIP32 child key derivation. Returns
+    /// an error if the tweak is out of range or the sum is zero -- the
+    /// exact edge cases BIP32 specifies skipping the derived index for.
+    #[inline]
+    pub fn add_assign<C>(&mut self, secp: &Secp256k1<C>, other: &SecretKey) -> Result<(), Error> {
+        unsafe {
+            if ffi::secp256k1_ec_privkey_tweak_add(secp.ctx, self.as_mut_ptr(), other.as_ptr()) != 1 {
+                return Err(Error::InvalidSecretKey);
+            }
+        }
+        Ok(())
+    }
+
+    /// Multiplies this secret key by `other`, modulo the curve order.
+    /// Returns an error if the tweak is out of range or the product is zero.
+    #[inline]
+    pub fn mul_assign<C>(&mut self, secp: &Secp256k1<C>, other: &SecretKey) -> Result<(), Error> {
+        unsafe {
+            if ffi::secp256k1_ec_privkey_tweak_mul(secp.ctx, self.as_mut_ptr(), other.as_ptr()) != 1 {
+                return Err(Error::InvalidSecretKey);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "SecretKey({:?})", &self[..])
+    }
+}
+
+impl fmt::Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(&self[..].to_hex())
+    }
+}
+
+impl FromStr for SecretKey {
+    type Err = Error;
+
+    /// Parses a hex-encoded secret key. Unlike `from_slice`, this has no
+    /// `Secp256k1` context to check the result is actually in the curve's
+    /// valid range with, so it only validates the length -- a zero or
+    /// out-of-range key can come back `Ok`. Prefer `from_slice` when a
+    /// context is available; the serde impls below do validate, since
+    /// they can afford a throwaway context.
+    fn from_str(s: &str) -> Result<SecretKey, Error> {
+        let raw: Vec<u8> = match s.from_hex() {
+            Ok(raw) => raw,
+            Err(_) => return Err(Error::InvalidSecretKey)
+        };
+        if raw.len() != constants::SECRET_KEY_SIZE {
+            return Err(Error::InvalidSecretKey);
+        }
+        let mut ret = [0; constants::SECRET_KEY_SIZE];
+        unsafe {
+            copy_nonoverlapping(raw.as_ptr(), ret.as_mut_ptr(), raw.len());
+        }
+        Ok(SecretKey(ret))
+    }
+}
+
+/// Checks that `data` is `SECRET_KEY_SIZE` bytes representing a scalar in
+/// the curve's valid range, without requiring a caller-supplied
+/// `Secp256k1` context. Only used by the serde deserializers below: a
+/// throwaway context is cheap here (`secp256k1_ec_seckey_verify` needs
+/// none of the expensive precomputed tables) and lets them reject invalid
+/// keys instead of silently admitting them, without resurrecting the
+/// implicit global context `Secp256k1<C>` replaced.
+#[cfg(feature = "serde")]
+fn seckey_verify_no_context(data: &[u8]) -> bool {
+    if data.len() != constants::SECRET_KEY_SIZE {
+        return false;
+    }
+    unsafe {
+        let ctx = ffi::secp256k1_context_create(0);
+        let res = ffi::secp256k1_ec_seckey_verify(ctx, data.as_ptr());
+        ffi::secp256k1_context_destroy(ctx);
+        res == 1
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SecretKey {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_bytes(&self[..])
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SecretKey {
+    // Unlike `FromStr` (which has no context to validate against at all),
+    // these deserializers reject out-of-range/zero keys via a throwaway
+    // context -- see `seckey_verify_no_context`.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<SecretKey, D::Error> {
+        if d.is_human_readable() {
+            struct HexVisitor;
+            impl<'de> de::Visitor<'de> for HexVisitor {
+                type Value = SecretKey;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a hex-encoded secret key")
+                }
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<SecretKey, E> {
+                    let raw: Vec<u8> = match v.from_hex() {
+                        Ok(raw) => raw,
+                        Err(_) => return Err(de::Error::invalid_value(de::Unexpected::Str(v), &self))
+                    };
+                    if !seckey_verify_no_context(&raw) {
+                        return Err(de::Error::invalid_value(de::Unexpected::Str(v), &self));
+                    }
+                    let mut ret = [0; constants::SECRET_KEY_SIZE];
+                    unsafe { copy_nonoverlapping(raw.as_ptr(), ret.as_mut_ptr(), raw.len()); }
+                    Ok(SecretKey(ret))
+                }
+            }
+            d.deserialize_str(HexVisitor)
+        } else {
+            struct BytesVisitor;
+            impl<'de> de::Visitor<'de> for BytesVisitor {
+                type Value = SecretKey;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("32 raw secret key bytes")
+                }
+                fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<SecretKey, E> {
+                    if !seckey_verify_no_context(v) {
+                        return Err(de::Error::invalid_value(de::Unexpected::Bytes(v), &self));
+                    }
+                    let mut ret = [0; constants::SECRET_KEY_SIZE];
+                    unsafe { copy_nonoverlapping(v.as_ptr(), ret.as_mut_ptr(), v.len()); }
+                    Ok(SecretKey(ret))
+                }
+            }
+            d.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+/// A public key, used to verify ECDSA signatures
+pub struct PublicKey(usize, [u8; constants::UNCOMPRESSED_PUBLIC_KEY_SIZE]);
+
+impl Copy for PublicKey {}
+
+impl PublicKey {
+    /// Creates an uninitialized public key with space for a public key of
+    /// the given compression
+    #[inline]
+    pub fn new(compressed: bool) -> PublicKey {
+        PublicKey(
+            if compressed { constants::COMPRESSED_PUBLIC_KEY_SIZE }
+            else { constants::UNCOMPRESSED_PUBLIC_KEY_SIZE },
+            [0; constants::UNCOMPRESSED_PUBLIC_KEY_SIZE])
+    }
+
+    /// Creates a new public key from a secret key
+    #[inline]
+    pub fn from_secret_key<C>(secp: &Secp256k1<C>, sk: &SecretKey, compressed: bool) -> PublicKey {
+        let mut pk = PublicKey::new(compressed);
+        let mut len = 0;
+        unsafe {
+            let res = ffi::secp256k1_ec_pubkey_create(
+                secp.ctx, pk.as_mut_ptr(), &mut len,
+                sk.as_ptr(), if compressed {1} else {0});
+            assert_eq!(res, 1);
+            assert_eq!(len as usize, pk.len());
+        }
+        pk
+    }
+
+    /// Converts a byte slice to a public key
+    #[inline]
+    pub fn from_slice(data: &[u8]) -> Result<PublicKey, Error> {
+        match data.len() {
+            constants::COMPRESSED_PUBLIC_KEY_SIZE |
+            constants::UNCOMPRESSED_PUBLIC_KEY_SIZE => {
+                let mut ret = [0; constants::UNCOMPRESSED_PUBLIC_KEY_SIZE];
+                unsafe {
+                    copy_nonoverlapping(data.as_ptr(),
+                                        ret.as_mut_ptr(),
+                                        data.len());
+                }
+                Ok(PublicKey(data.len(), ret))
+            }
+            _ => Err(Error::InvalidPublicKey)
+        }
+    }
+
+    /// Returns the length of the public key
+    #[inline]
+    pub fn len(&self) -> usize {
+        let &PublicKey(len, _) = self;
+        len
+    }
+
+    /// Converts the public key to a raw pointer suitable for use
+    /// with the FFI functions
+    #[inline]
+    pub fn as_ptr(&self) -> *const u8 {
+        let &PublicKey(_, ref data) = self;
+        data.as_ptr()
+    }
+
+    /// Converts the public key to a mutable raw pointer suitable for
+    /// use with the FFI functions
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        let &mut PublicKey(_, ref mut data) = self;
+        data.as_mut_ptr()
+    }
+
+    /// Adds `tweak * G` to this public key in place. This is the public
+    /// counterpart of `SecretKey::add_assign`, letting a watch-only wallet
+    /// derive a child public key without the parent secret key. Returns
+    /// an error if the tweak is out of range or the result is the point
+    /// at infinity.
+    #[inline]
+    pub fn add_exp_assign<C>(&mut self, secp: &Secp256k1<C>, tweak: &[u8]) -> Result<(), Error> {
+        if tweak.len() != constants::SECRET_KEY_SIZE {
+            return Err(Error::InvalidSecretKey);
+        }
+        let len = self.len() as c_int;
+        unsafe {
+            if ffi::secp256k1_ec_pubkey_tweak_add(secp.ctx, self.as_mut_ptr(), len, tweak.as_ptr()) != 1 {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        Ok(())
+    }
+
+    /// Multiplies this public key in place by `tweak`. Returns an error
+    /// if the tweak is out of range or the result is the point at infinity.
+    #[inline]
+    pub fn mul_assign<C>(&mut self, secp: &Secp256k1<C>, tweak: &[u8]) -> Result<(), Error> {
+        if tweak.len() != constants::SECRET_KEY_SIZE {
+            return Err(Error::InvalidSecretKey);
+        }
+        let len = self.len() as c_int;
+        unsafe {
+            if ffi::secp256k1_ec_pubkey_tweak_mul(secp.ctx, self.as_mut_ptr(), len, tweak.as_ptr()) != 1 {
+                return Err(Error::InvalidPublicKey);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the given public keys together via elliptic-curve point
+    /// addition, returning their sum. This is the primitive behind
+    /// multi-party key aggregation and simple threshold schemes. Accepts
+    /// a mix of compressed and uncompressed inputs; `compressed` selects
+    /// the encoding of the result. Returns an error if the sum is the
+    /// point at infinity.
+    pub fn combine<C>(secp: &Secp256k1<C>, keys: &[PublicKey], compressed: bool)
+                      -> Result<PublicKey, Error> {
+        let ptrs: Vec<*const u8> = keys.iter().map(|pk| pk.as_ptr()).collect();
+        let lens: Vec<c_int> = keys.iter().map(|pk| pk.len() as c_int).collect();
+
+        let mut pk = PublicKey::new(compressed);
+        let mut len = 0;
+        unsafe {
+            let res = ffi::secp256k1_ec_pubkey_combine(secp.ctx, pk.as_mut_ptr(), &mut len,
+                                                        ptrs.as_ptr(), lens.as_ptr(),
+                                                        keys.len() as c_int,
+                                                        if compressed {1} else {0});
+            if res != 1 {
+                return Err(Error::InvalidPublicKey);
+            }
+            assert_eq!(len as usize, pk.len());
+        }
+        Ok(pk)
+    }
+}
+
+impl PartialEq for PublicKey {
+    #[inline]
+    fn eq(&self, other: &PublicKey) -> bool {
+        self.len() == other.len() && &self[..] == &other[..]
+    }
+}
+impl Eq for PublicKey {}
+
+impl Clone for PublicKey {
+    #[inline]
+    fn clone(&self) -> PublicKey {
+        let &PublicKey(len, data) = self;
+        PublicKey(len, data)
+    }
+}
+
+impl ops::Index<ops::RangeFull> for PublicKey {
+    type Output = [u8];
+
+    #[inline]
+    fn index(&self, _: ops::RangeFull) -> &[u8] {
+        let &PublicKey(len, ref data) = self;
+        &data[..len]
+    }
+}
+
+impl fmt::Debug for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "PublicKey({:?})", &self[..])
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(&self[..].to_hex())
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    /// Parses a hex-encoded public key, compressed or uncompressed
+    fn from_str(s: &str) -> Result<PublicKey, Error> {
+        let raw: Vec<u8> = match s.from_hex() {
+            Ok(raw) => raw,
+            Err(_) => return Err(Error::InvalidPublicKey)
+        };
+        PublicKey::from_slice(&raw)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_bytes(&self[..])
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<PublicKey, D::Error> {
+        if d.is_human_readable() {
+            struct HexVisitor;
+            impl<'de> de::Visitor<'de> for HexVisitor {
+                type Value = PublicKey;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a hex-encoded public key")
+                }
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<PublicKey, E> {
+                    v.parse().map_err(de::Error::custom)
+                }
+            }
+            d.deserialize_str(HexVisitor)
+        } else {
+            struct BytesVisitor;
+            impl<'de> de::Visitor<'de> for BytesVisitor {
+                type Value = PublicKey;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a raw compressed or uncompressed public key")
+                }
+                fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<PublicKey, E> {
+                    PublicKey::from_slice(v).map_err(de::Error::custom)
+                }
+            }
+            d.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
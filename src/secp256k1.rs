@@ -37,22 +37,30 @@
 
 extern crate crypto;
 extern crate rustc_serialize as serialize;
+#[cfg(feature = "serde")] extern crate serde;
 #[cfg(test)] extern crate test;
 
 extern crate libc;
 extern crate rand;
 
 use std::intrinsics::copy_nonoverlapping;
+use std::marker::PhantomData;
+use std::str::FromStr;
 use std::{fmt, io, ops, ptr};
-use std::sync::{Once, ONCE_INIT};
 use libc::c_int;
 use rand::{OsRng, Rng, SeedableRng};
+use serialize::hex::{FromHex, ToHex};
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de;
 
 use crypto::fortuna::Fortuna;
 
 #[macro_use]
 mod macros;
 pub mod constants;
+pub mod ecdh;
 pub mod ffi;
 pub mod key;
 
@@ -106,6 +114,119 @@ impl Signature {
             Err(Error::InvalidSignature)
         }
     }
+
+    /// Serializes the signature in strict DER format, the encoding
+    /// Bitcoin transactions carry on the wire. Fails with `InvalidSignature`
+    /// if this `Signature` isn't actually DER to begin with -- e.g. one
+    /// produced by `sign_compact`, or built via `from_slice` from bytes
+    /// that were never DER in the first place.
+    pub fn serialize_der<C>(&self, secp: &Secp256k1<C>) -> Result<Vec<u8>, Error> {
+        let compact = try!(self.as_compact(secp));
+
+        let mut ret = [0; constants::MAX_SIGNATURE_SIZE];
+        let mut len = constants::MAX_SIGNATURE_SIZE as c_int;
+        unsafe {
+            let res = ffi::secp256k1_ecdsa_sig_serialize(secp.ctx, ret.as_mut_ptr(),
+                                                          &mut len,
+                                                          compact.as_ptr());
+            assert_eq!(res, 1);
+        }
+        Ok(ret[..len as usize].to_vec())
+    }
+
+    /// Converts a DER-encoded byte slice to a signature. Use this, rather
+    /// than `from_slice`, to parse signatures off the wire.
+    #[inline]
+    pub fn from_der<C>(secp: &Secp256k1<C>, data: &[u8]) -> Result<Signature, Error> {
+        Signature::parse_der(secp, data, false)
+    }
+
+    /// Converts a DER-encoded byte slice to a signature, tolerating the
+    /// zero-padded and overlong-length encodings found in historical,
+    /// pre-BIP66 transactions. Use this when verifying old data; `verify`
+    /// backed by `from_der` alone cannot be used for consensus checking
+    /// since libsecp256k1's strict parser rejects those transactions.
+    #[inline]
+    pub fn from_der_lax<C>(secp: &Secp256k1<C>, data: &[u8]) -> Result<Signature, Error> {
+        Signature::parse_der(secp, data, true)
+    }
+
+    fn parse_der<C>(secp: &Secp256k1<C>, data: &[u8], lax: bool) -> Result<Signature, Error> {
+        let mut compact = [0; 64];
+        let res = unsafe {
+            if lax {
+                ffi::secp256k1_ecdsa_sig_parse_lax(secp.ctx, compact.as_mut_ptr(),
+                                                   data.as_ptr(),
+                                                   data.len() as c_int)
+            } else {
+                ffi::secp256k1_ecdsa_sig_parse(secp.ctx, compact.as_mut_ptr(),
+                                               data.as_ptr(),
+                                               data.len() as c_int)
+            }
+        };
+        if res != 1 {
+            return Err(Error::InvalidSignature);
+        }
+
+        let mut der = [0; constants::MAX_SIGNATURE_SIZE];
+        let mut len = constants::MAX_SIGNATURE_SIZE as c_int;
+        unsafe {
+            if ffi::secp256k1_ecdsa_sig_serialize(secp.ctx, der.as_mut_ptr(), &mut len,
+                                                  compact.as_ptr()) != 1 {
+                return Err(Error::InvalidSignature);
+            }
+        }
+        Ok(Signature(len as usize, der))
+    }
+
+    /// Normalizes the S component of the signature into the lower half of
+    /// the curve order, flipping `S -> n - S` when `S > n/2`. This is the
+    /// BIP-62 canonicalization step: `(r, s)` and `(r, n - s)` are both
+    /// valid signatures for the same message and key, so after `sign`,
+    /// calling this guarantees the emitted signature is non-malleable.
+    /// Fails with `InvalidSignature` if this `Signature` isn't DER to begin
+    /// with -- e.g. one produced by `sign_compact`.
+    pub fn normalize_s<C>(&mut self, secp: &Secp256k1<C>) -> Result<(), Error> {
+        let mut compact = try!(self.as_compact(secp));
+        unsafe { ffi::secp256k1_ecdsa_sig_normalize(secp.ctx, compact.as_mut_ptr()); }
+
+        let mut der = [0; constants::MAX_SIGNATURE_SIZE];
+        let mut len = constants::MAX_SIGNATURE_SIZE as c_int;
+        unsafe {
+            let res = ffi::secp256k1_ecdsa_sig_serialize(secp.ctx, der.as_mut_ptr(), &mut len,
+                                                         compact.as_ptr());
+            assert_eq!(res, 1);
+        }
+        *self = Signature(len as usize, der);
+        Ok(())
+    }
+
+    /// Returns whether the S component of this signature already lies in
+    /// the lower half of the curve order, i.e. whether `normalize_s` on
+    /// it would be a no-op. Fails with `InvalidSignature` if this
+    /// `Signature` isn't DER to begin with -- e.g. one produced by
+    /// `sign_compact`.
+    pub fn is_normalized<C>(&self, secp: &Secp256k1<C>) -> Result<bool, Error> {
+        let mut compact = try!(self.as_compact(secp));
+        Ok(unsafe { ffi::secp256k1_ecdsa_sig_normalize(secp.ctx, compact.as_mut_ptr()) == 0 })
+    }
+
+    /// Parses this signature's DER encoding into the library's internal
+    /// 64-byte compact (r, s) representation. Fails with `InvalidSignature`
+    /// if the signature's raw bytes aren't actually DER -- e.g. a compact
+    /// signature from `sign_compact`, or arbitrary bytes from `from_slice`.
+    fn as_compact<C>(&self, secp: &Secp256k1<C>) -> Result<[u8; 64], Error> {
+        let mut compact = [0; 64];
+        unsafe {
+            let res = ffi::secp256k1_ecdsa_sig_parse(secp.ctx, compact.as_mut_ptr(),
+                                                      self.as_ptr(),
+                                                      self.len() as c_int);
+            if res != 1 {
+                return Err(Error::InvalidSignature);
+            }
+        }
+        Ok(compact)
+    }
 }
 
 impl ops::Index<usize> for Signature {
@@ -162,6 +283,113 @@ impl Clone for Signature {
     }
 }
 
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str(&self[0..self.len()].to_hex())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = Error;
+
+    /// Parses a hex-encoded signature. The bytes are taken as-is, the
+    /// same as `from_slice` -- for a signature produced by `sign` or
+    /// `from_der` this is DER, but `from_str` has no `Secp256k1` context
+    /// to reinterpret anything else through.
+    fn from_str(s: &str) -> Result<Signature, Error> {
+        let raw: Vec<u8> = match s.from_hex() {
+            Ok(raw) => raw,
+            Err(_) => return Err(Error::InvalidSignature)
+        };
+        Signature::from_slice(&raw)
+    }
+}
+
+/// Parses strict DER bytes into a `Signature` using a throwaway context,
+/// for callers (the serde `Deserialize` impl below) with no `Secp256k1`
+/// of their own to validate against. Mirrors `parse_der`/`as_compact`;
+/// a throwaway context is fine here since DER parsing needs none of the
+/// expensive precomputed tables.
+#[cfg(feature = "serde")]
+fn parse_der_no_context(data: &[u8]) -> Result<Signature, Error> {
+    unsafe {
+        let ctx = ffi::secp256k1_context_create(0);
+
+        let mut compact = [0; 64];
+        let res = ffi::secp256k1_ecdsa_sig_parse(ctx, compact.as_mut_ptr(),
+                                                  data.as_ptr(), data.len() as c_int);
+        if res != 1 {
+            ffi::secp256k1_context_destroy(ctx);
+            return Err(Error::InvalidSignature);
+        }
+
+        let mut der = [0; constants::MAX_SIGNATURE_SIZE];
+        let mut len = constants::MAX_SIGNATURE_SIZE as c_int;
+        let res = ffi::secp256k1_ecdsa_sig_serialize(ctx, der.as_mut_ptr(), &mut len,
+                                                      compact.as_ptr());
+        ffi::secp256k1_context_destroy(ctx);
+        if res != 1 {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(Signature(len as usize, der))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Signature {
+    // NOTE: this emits the signature's raw stored bytes, not necessarily
+    // DER -- `Serialize` has no `Secp256k1` context to reserialize through
+    // `serialize_der` with. For a `Signature` produced by `sign` or
+    // `from_der` the raw bytes already are DER; one from `sign_compact`
+    // is not. Call `serialize_der(&secp)` yourself first if the wire
+    // format must be guaranteed DER.
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.collect_str(self)
+        } else {
+            s.serialize_bytes(&self[0..self.len()])
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Signature {
+    // Routes through `parse_der_no_context` rather than `from_slice`, so
+    // this only accepts strict DER -- matching `serialize_der`/`from_der`
+    // and the validation the `SecretKey` deserializer does.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Signature, D::Error> {
+        if d.is_human_readable() {
+            struct HexVisitor;
+            impl<'de> de::Visitor<'de> for HexVisitor {
+                type Value = Signature;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a hex-encoded DER signature")
+                }
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Signature, E> {
+                    let raw: Vec<u8> = match v.from_hex() {
+                        Ok(raw) => raw,
+                        Err(_) => return Err(de::Error::invalid_value(de::Unexpected::Str(v), &self))
+                    };
+                    parse_der_no_context(&raw).map_err(|_| de::Error::invalid_value(de::Unexpected::Str(v), &self))
+                }
+            }
+            d.deserialize_str(HexVisitor)
+        } else {
+            struct BytesVisitor;
+            impl<'de> de::Visitor<'de> for BytesVisitor {
+                type Value = Signature;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("raw DER signature bytes")
+                }
+                fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Signature, E> {
+                    parse_der_no_context(v).map_err(|_| de::Error::invalid_value(de::Unexpected::Bytes(v), &self))
+                }
+            }
+            d.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
 /// A (hashed) message input to an ECDSA signature
 pub struct Message([u8; constants::MESSAGE_SIZE]);
 impl_array_newtype!(Message, u8, constants::MESSAGE_SIZE);
@@ -211,45 +439,121 @@ impl fmt::Display for Error {
     }
 }
 
-static mut Secp256k1_init: Once = ONCE_INIT;
-
-/// The secp256k1 engine, used to execute all signature operations
-pub struct Secp256k1 {
-    rng: Fortuna
+/// Marker trait for `Secp256k1` contexts that can sign
+pub trait Signing {}
+/// Marker trait for `Secp256k1` contexts that can verify
+pub trait Verification {}
+
+/// Marker type for a context that can only sign
+pub struct SignOnly {}
+/// Marker type for a context that can only verify
+pub struct VerifyOnly {}
+/// Marker type for a context that can both sign and verify
+pub struct Full {}
+
+impl Signing for SignOnly {}
+impl Signing for Full {}
+
+impl Verification for VerifyOnly {}
+impl Verification for Full {}
+
+/// The secp256k1 engine, used to execute all signature operations. The
+/// capability marker `C` (one of `SignOnly`, `VerifyOnly` or `Full`)
+/// determines which of `sign`/`verify` are available: a verify-only
+/// context never allocates the (expensive) signing precomputation
+/// tables, and vice versa.
+pub struct Secp256k1<C> {
+    ctx: *mut ffi::Context,
+    rng: Fortuna,
+    phantom: PhantomData<C>
 }
 
-/// Does one-time initialization of the secp256k1 engine. Can be called
-/// multiple times, and is called by the `Secp256k1` constructor. This
-/// only needs to be called directly if you are using the library without
-/// a `Secp256k1` object, e.g. batch key generation through
-/// `key::PublicKey::from_secret_key`.
-pub fn init() {
-    unsafe {
-        Secp256k1_init.call_once(|| {
-            ffi::secp256k1_start(ffi::SECP256K1_START_VERIFY |
-                                 ffi::SECP256K1_START_SIGN);
-        });
+// The FFI context is only ever mutated through `&mut self` (`randomize`)
+// or torn down through `&mut self` (`Drop`); through a shared reference it
+// is only read, by FFI calls that take it as `*const Context`, so sharing
+// a `Secp256k1` across threads, or sending one to another thread, is sound.
+unsafe impl<C> Send for Secp256k1<C> {}
+unsafe impl<C> Sync for Secp256k1<C> {}
+
+impl<C> Drop for Secp256k1<C> {
+    fn drop(&mut self) {
+        unsafe { ffi::secp256k1_context_destroy(self.ctx); }
     }
 }
 
-impl Secp256k1 {
-    /// Constructs a new secp256k1 engine.
-    pub fn new() -> io::Result<Secp256k1> {
-        init();
+impl<C> Secp256k1<C> {
+    /// Creates a context with the given set of libsecp256k1 context flags,
+    /// seeding its random-number generator and context randomization from
+    /// the OS RNG.
+    fn with_flags(flags: c_int) -> io::Result<Secp256k1<C>> {
         let mut osrng = try!(OsRng::new());
         let mut seed = [0; 2048];
         osrng.fill_bytes(&mut seed);
-        Ok(Secp256k1 { rng: SeedableRng::from_seed(&seed[..]) })
+
+        let ctx = unsafe { ffi::secp256k1_context_create(flags) };
+        let mut ret = Secp256k1 {
+            ctx: ctx,
+            rng: SeedableRng::from_seed(&seed[..]),
+            phantom: PhantomData
+        };
+        ret.randomize(&mut osrng);
+        Ok(ret)
+    }
+
+    /// Re-randomizes the context, hardening it against side-channel
+    /// attacks that try to exploit the precomputed tables. Safe (and
+    /// cheap) to call again at any time.
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) {
+        let mut seed = [0; 32];
+        rng.fill_bytes(&mut seed);
+        unsafe {
+            ffi::secp256k1_context_randomize(self.ctx, seed.as_ptr());
+        }
+    }
+}
+
+impl Secp256k1<Full> {
+    /// Constructs a new secp256k1 engine capable of both signing and verifying
+    pub fn new() -> io::Result<Secp256k1<Full>> {
+        Secp256k1::with_flags(ffi::SECP256K1_START_SIGN | ffi::SECP256K1_START_VERIFY)
+    }
+}
+
+impl Secp256k1<SignOnly> {
+    /// Constructs a new secp256k1 engine that can only sign, skipping the
+    /// allocation of the (unneeded) verification precomputation tables
+    pub fn signing_only() -> io::Result<Secp256k1<SignOnly>> {
+        Secp256k1::with_flags(ffi::SECP256K1_START_SIGN)
     }
+}
 
+impl Secp256k1<VerifyOnly> {
+    /// Constructs a new secp256k1 engine that can only verify, skipping the
+    /// allocation of the (unneeded) signing precomputation tables
+    pub fn verification_only() -> io::Result<Secp256k1<VerifyOnly>> {
+        Secp256k1::with_flags(ffi::SECP256K1_START_VERIFY)
+    }
+}
+
+impl<C: Signing> Secp256k1<C> {
     /// Generates a random keypair. Convenience function for `key::SecretKey::new`
     /// and `key::PublicKey::from_secret_key`; call those functions directly for
     /// batch key generation.
     #[inline]
     pub fn generate_keypair(&mut self, compressed: bool)
                             -> (key::SecretKey, key::PublicKey) {
-        let sk = key::SecretKey::new(&mut self.rng);
-        let pk = key::PublicKey::from_secret_key(&sk, compressed);
+        let sk = {
+            let ctx = self.ctx;
+            let mut ret = [0; constants::SECRET_KEY_SIZE];
+            loop {
+                self.rng.fill_bytes(&mut ret);
+                if unsafe { ffi::secp256k1_ec_seckey_verify(ctx, ret.as_ptr()) } == 1 {
+                    break;
+                }
+            }
+            key::SecretKey::from_slice(self, &ret).unwrap()
+        };
+        let pk = key::PublicKey::from_secret_key(self, &sk, compressed);
         (sk, pk)
     }
 
@@ -259,7 +563,7 @@ impl Secp256k1 {
         let mut sig = [0; constants::MAX_SIGNATURE_SIZE];
         let mut len = constants::MAX_SIGNATURE_SIZE as c_int;
         unsafe {
-            if ffi::secp256k1_ecdsa_sign(msg.as_ptr(), (&mut sig).as_mut_ptr(),
+            if ffi::secp256k1_ecdsa_sign(self.ctx, msg.as_ptr(), (&mut sig).as_mut_ptr(),
                                          &mut len, sk.as_ptr(),
                                          ffi::secp256k1_nonce_function_rfc6979,
                                          ptr::null()) != 1 {
@@ -277,7 +581,7 @@ impl Secp256k1 {
         let mut sig = [0; constants::MAX_SIGNATURE_SIZE];
         let mut recid = 0;
         unsafe {
-            if ffi::secp256k1_ecdsa_sign_compact(msg.as_ptr(),
+            if ffi::secp256k1_ecdsa_sign_compact(self.ctx, msg.as_ptr(),
                                                  sig.as_mut_ptr(), sk.as_ptr(),
                                                  ffi::secp256k1_nonce_function_default,
                                                  ptr::null(), &mut recid) != 1 {
@@ -286,7 +590,9 @@ impl Secp256k1 {
         };
         Ok((Signature(constants::MAX_COMPACT_SIGNATURE_SIZE, sig), RecoveryId(recid)))
     }
+}
 
+impl<C: Verification> Secp256k1<C> {
     /// Determines the public key for which `sig` is a valid signature for
     /// `msg`. Returns through the out-pointer `pubkey`.
     pub fn recover_compact(&self, msg: &Message, sig: &[u8],
@@ -297,7 +603,7 @@ impl Secp256k1 {
 
         unsafe {
             let mut len = 0;
-            if ffi::secp256k1_ecdsa_recover_compact(msg.as_ptr(),
+            if ffi::secp256k1_ecdsa_recover_compact(self.ctx, msg.as_ptr(),
                                                     sig.as_ptr(), pk.as_mut_ptr(), &mut len,
                                                     if compressed {1} else {0},
                                                     recid) != 1 {
@@ -309,22 +615,21 @@ impl Secp256k1 {
     }
 
     /// Checks that `sig` is a valid ECDSA signature for `msg` using the public
-    /// key `pubkey`. Returns `Ok(true)` on success. Note that this function cannot
-    /// be used for Bitcoin consensus checking since there are transactions out
-    /// there with zero-padded signatures that don't fit in the `Signature` type.
-    /// Use `verify_raw` instead.
+    /// key `pk`. Returns `Ok(())` on success. Transactions out there with
+    /// zero-padded or overlong signatures will fail `Signature::from_der`; parse
+    /// those with `Signature::from_der_lax` first if consensus checking against
+    /// historical data is required.
     #[inline]
-    pub fn verify(msg: &Message, sig: &Signature, pk: &key::PublicKey) -> Result<(), Error> {
-        Secp256k1::verify_raw(msg, &sig[..], pk)
+    pub fn verify(&self, msg: &Message, sig: &Signature, pk: &key::PublicKey) -> Result<(), Error> {
+        self.verify_raw(msg, &sig[..], pk)
     }
 
     /// Checks that `sig` is a valid ECDSA signature for `msg` using the public
     /// key `pubkey`. Returns `Ok(true)` on success.
     #[inline]
-    pub fn verify_raw(msg: &Message, sig: &[u8], pk: &key::PublicKey) -> Result<(), Error> {
-        init();  // This is a static function, so we have to init
+    pub fn verify_raw(&self, msg: &Message, sig: &[u8], pk: &key::PublicKey) -> Result<(), Error> {
         let res = unsafe {
-            ffi::secp256k1_ecdsa_verify(msg.as_ptr(),
+            ffi::secp256k1_ecdsa_verify(self.ctx, msg.as_ptr(),
                                         sig.as_ptr(), sig.len() as c_int,
                                         pk.as_ptr(), pk.len() as c_int)
         };
@@ -347,19 +652,21 @@ mod tests {
 
     use test::{Bencher, black_box};
 
+    use ecdh::SharedSecret;
     use key::PublicKey;
     use super::{Secp256k1, Signature, Message};
     use super::Error::{InvalidPublicKey, IncorrectSignature, InvalidSignature};
 
     #[test]
     fn invalid_pubkey() {
+        let s = Secp256k1::new().unwrap();
         let sig = Signature::from_slice(&[0; 72]).unwrap();
         let pk = PublicKey::new(true);
         let mut msg = [0u8; 32];
         thread_rng().fill_bytes(&mut msg);
         let msg = Message::from_slice(&msg).unwrap();
 
-        assert_eq!(Secp256k1::verify(&msg, &sig, &pk), Err(InvalidPublicKey));
+        assert_eq!(s.verify(&msg, &sig, &pk), Err(InvalidPublicKey));
     }
 
     #[test]
@@ -373,7 +680,7 @@ mod tests {
         thread_rng().fill_bytes(&mut msg);
         let msg = Message::from_slice(&msg).unwrap();
 
-        assert_eq!(Secp256k1::verify(&msg, &sig, &pk), Err(InvalidSignature));
+        assert_eq!(s.verify(&msg, &sig, &pk), Err(InvalidSignature));
     }
 
     #[test]
@@ -386,7 +693,7 @@ mod tests {
         thread_rng().fill_bytes(&mut msg);
         let msg = Message::from_slice(&msg).unwrap();
 
-        assert_eq!(Secp256k1::verify(&msg, &sig, &pk), Err(InvalidSignature));
+        assert_eq!(s.verify(&msg, &sig, &pk), Err(InvalidSignature));
     }
 
     #[test]
@@ -414,7 +721,7 @@ mod tests {
 
         let sig = s.sign(&msg, &sk).unwrap();
 
-        assert_eq!(Secp256k1::verify(&msg, &sig, &pk), Ok(()));
+        assert_eq!(s.verify(&msg, &sig, &pk), Ok(()));
     }
 
     #[test]
@@ -432,7 +739,97 @@ mod tests {
         let mut msg = [0u8; 32];
         thread_rng().fill_bytes(&mut msg);
         let msg = Message::from_slice(&msg).unwrap();
-        assert_eq!(Secp256k1::verify(&msg, &sig, &pk), Err(IncorrectSignature));
+        assert_eq!(s.verify(&msg, &sig, &pk), Err(IncorrectSignature));
+    }
+
+    #[test]
+    fn signature_der_roundtrip() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let mut msg = [0u8; 32];
+        thread_rng().fill_bytes(&mut msg);
+        let msg = Message::from_slice(&msg).unwrap();
+
+        let (sk, _) = s.generate_keypair(false);
+        let sig = s.sign(&msg, &sk).unwrap();
+
+        let der = sig.serialize_der(&s).unwrap();
+        let sig2 = Signature::from_der(&s, &der[..]).unwrap();
+        assert_eq!(sig2.serialize_der(&s).unwrap(), der);
+    }
+
+    #[test]
+    fn signature_from_der_lax_accepts_strict_der() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let mut msg = [0u8; 32];
+        thread_rng().fill_bytes(&mut msg);
+        let msg = Message::from_slice(&msg).unwrap();
+
+        let (sk, _) = s.generate_keypair(false);
+        let sig = s.sign(&msg, &sk).unwrap();
+
+        let der = sig.serialize_der(&s).unwrap();
+        assert!(Signature::from_der_lax(&s, &der[..]).is_ok());
+    }
+
+    #[test]
+    fn signature_normalize_s_is_idempotent() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let mut msg = [0u8; 32];
+        thread_rng().fill_bytes(&mut msg);
+        let msg = Message::from_slice(&msg).unwrap();
+
+        let (sk, _) = s.generate_keypair(false);
+        let mut sig = s.sign(&msg, &sk).unwrap();
+
+        sig.normalize_s(&s).unwrap();
+        assert!(sig.is_normalized(&s).unwrap());
+
+        let normalized_der = sig.serialize_der(&s).unwrap();
+        sig.normalize_s(&s).unwrap();
+        assert_eq!(sig.serialize_der(&s).unwrap(), normalized_der);
+    }
+
+    #[test]
+    fn tweak_add_matches_pubkey_tweak_add() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let (mut sk, mut pk) = s.generate_keypair(false);
+        let (tweak, _) = s.generate_keypair(false);
+
+        sk.add_assign(&s, &tweak).unwrap();
+        pk.add_exp_assign(&s, &tweak[..]).unwrap();
+
+        assert_eq!(PublicKey::from_secret_key(&s, &sk, false), pk);
+    }
+
+    #[test]
+    fn tweak_mul_matches_pubkey_tweak_mul() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let (mut sk, mut pk) = s.generate_keypair(false);
+        let (tweak, _) = s.generate_keypair(false);
+
+        sk.mul_assign(&s, &tweak).unwrap();
+        pk.mul_assign(&s, &tweak[..]).unwrap();
+
+        assert_eq!(PublicKey::from_secret_key(&s, &sk, false), pk);
+    }
+
+    #[test]
+    fn combine_two_pubkeys_matches_tweaked_key() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let (sk1, pk1) = s.generate_keypair(true);
+        let (sk2, pk2) = s.generate_keypair(true);
+
+        let combined = PublicKey::combine(&s, &[pk1, pk2], true).unwrap();
+
+        let mut sk_sum = sk1;
+        sk_sum.add_assign(&s, &sk2).unwrap();
+        assert_eq!(PublicKey::from_secret_key(&s, &sk_sum, true), combined);
     }
 
     #[test]
@@ -450,6 +847,61 @@ mod tests {
         assert_eq!(s.recover_compact(&msg, &sig[..], false, recid), Ok(pk));
     }
 
+    #[test]
+    fn sign_with_sign_only_verify_with_verify_only() {
+        let mut sign_ctx = Secp256k1::signing_only().unwrap();
+        let verify_ctx = Secp256k1::verification_only().unwrap();
+
+        let mut msg = [0u8; 32];
+        thread_rng().fill_bytes(&mut msg);
+        let msg = Message::from_slice(&msg).unwrap();
+
+        let (sk, pk) = sign_ctx.generate_keypair(false);
+        let sig = sign_ctx.sign(&msg, &sk).unwrap();
+
+        assert_eq!(verify_ctx.verify(&msg, &sig, &pk), Ok(()));
+    }
+
+    #[test]
+    fn ecdh_agrees_both_directions() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let (sk1, pk1) = s.generate_keypair(true);
+        let (sk2, pk2) = s.generate_keypair(true);
+
+        let shared1 = SharedSecret::new(&s, &pk2, &sk1).unwrap();
+        let shared2 = SharedSecret::new(&s, &pk1, &sk2).unwrap();
+        assert_eq!(shared1.as_slice(), shared2.as_slice());
+    }
+
+    #[test]
+    fn ecdh_new_raw_matches_pubkey_mul_assign() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let (sk1, _) = s.generate_keypair(true);
+        let (_, mut pk2) = s.generate_keypair(true);
+
+        let raw = SharedSecret::new_raw(&s, &pk2, &sk1).unwrap();
+        pk2.mul_assign(&s, &sk1[..]).unwrap();
+        assert_eq!(raw, pk2);
+    }
+
+    #[test]
+    fn hex_roundtrip_keys_and_signature() {
+        let mut s = Secp256k1::new().unwrap();
+
+        let mut msg = [0u8; 32];
+        thread_rng().fill_bytes(&mut msg);
+        let msg = Message::from_slice(&msg).unwrap();
+
+        let (sk, pk) = s.generate_keypair(true);
+        let sig = s.sign(&msg, &sk).unwrap();
+
+        assert_eq!(sk.to_string().parse::<::key::SecretKey>().unwrap(), sk);
+        assert_eq!(pk.to_string().parse::<PublicKey>().unwrap(), pk);
+        assert_eq!(&sig.to_string().parse::<Signature>().unwrap()[..], &sig[..]);
+    }
+
     #[bench]
     pub fn generate_compressed(bh: &mut Bencher) {
         let mut s = Secp256k1::new().unwrap();